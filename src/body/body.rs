@@ -569,6 +569,18 @@ impl Sender {
     }
 
     /// Send trailers on trailers channel.
+    ///
+    /// When the body is a response written by an HTTP/1 server, trailers
+    /// are only sent if all of the following hold, and are otherwise
+    /// dropped without an error:
+    ///
+    /// - the response body uses chunked encoding, so it has no
+    ///   `Content-Length`;
+    /// - the response declares each field in a `Trailer` header;
+    /// - the request accepted trailers with `TE: trailers`.
+    ///
+    /// Fields that aren't allowed in trailers, such as `Content-Length`,
+    /// `Host` or `Authorization`, are always dropped.
     pub async fn send_trailers(&mut self, trailers: HeaderMap) -> crate::Result<()> {
         let tx = match self.trailers_tx.take() {
             Some(tx) => tx,
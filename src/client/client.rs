@@ -930,6 +930,23 @@ impl Builder {
 
     // HTTP/1 options
 
+    /// Set whether HTTP/1 connections should try to use vectored writes,
+    /// or always flatten into a single buffer.
+    ///
+    /// Note that setting this to false may mean more copies of body data,
+    /// but may also improve performance when an IO transport doesn't
+    /// support vectored writes well, such as most TLS implementations.
+    ///
+    /// Setting this to true will force hyper to use queued strategy
+    /// which may eliminate unnecessary cloning on some TLS backends
+    ///
+    /// Default is `auto`. In this mode hyper will try to guess which
+    /// mode to use
+    pub fn http1_writev(&mut self, enabled: bool) -> &mut Self {
+        self.conn_builder.h1_writev(enabled);
+        self
+    }
+
     /// Sets the exact size of the read buffer to *always* use.
     ///
     /// Note that setting this option unsets the `http1_max_buf_size` option.
@@ -967,6 +984,41 @@ impl Builder {
         self
     }
 
+    /// Set whether HTTP/1 connections will remember the original casing of
+    /// received header names.
+    ///
+    /// The casing is kept alongside the response, and is used again when a
+    /// message carrying it (such as a response relayed by a proxy) is
+    /// written out, so header names go back on the wire as they came in.
+    /// Only the casing of names, and the order of repeated values of one
+    /// name, are preserved; headers with different names are still written
+    /// in `HeaderMap` order.
+    ///
+    /// The casing is stored in the response's `Extensions`, so it only
+    /// survives if those extensions are moved into the outgoing message. A
+    /// message rebuilt with `Request::builder()` or `Response::builder()`
+    /// loses it.
+    ///
+    /// Note that this setting does not affect HTTP/2.
+    ///
+    /// Default is false.
+    pub fn http1_preserve_header_case(&mut self, val: bool) -> &mut Self {
+        self.conn_builder.h1_preserve_header_case(val);
+        self
+    }
+
+    /// Set the maximum number of headers allowed in an HTTP/1 message head.
+    ///
+    /// Messages with more headers are rejected as too large. When left
+    /// unset, a limit of 100 is used and the header slots are kept on the
+    /// stack; setting this option allocates them on the heap instead.
+    ///
+    /// Default is 100.
+    pub fn http1_max_headers(&mut self, max: usize) -> &mut Self {
+        self.conn_builder.h1_max_headers(max);
+        self
+    }
+
     /// Set whether the connection **must** use HTTP/2.
     ///
     /// The destination must either allow HTTP2 Prior Knowledge, or the
@@ -23,6 +23,9 @@ pub(super) enum Kind {
     User(User),
     /// A message reached EOF, but is not complete.
     IncompleteMessage,
+    /// A client did not send a complete message head in time.
+    #[cfg(all(feature = "http1", feature = "server", feature = "runtime"))]
+    HeaderTimeout,
     /// A connection received a message (or bytes) when not waiting for one.
     #[cfg(feature = "http1")]
     UnexpectedMessage,
@@ -73,6 +76,8 @@ pub(super) enum Parse {
     Header,
     TooLarge,
     Status,
+    #[cfg(all(feature = "http1", feature = "server"))]
+    Expect,
 }
 
 #[derive(Debug, PartialEq)]
@@ -229,6 +234,11 @@ impl Error {
         Error::new(Kind::Parse(Parse::VersionH2))
     }
 
+    #[cfg(all(feature = "http1", feature = "server", feature = "runtime"))]
+    pub(super) fn new_header_timeout() -> Error {
+        Error::new(Kind::HeaderTimeout).with(TimedOut)
+    }
+
     #[cfg(feature = "http1")]
     pub(super) fn new_unexpected_message() -> Error {
         Error::new(Kind::UnexpectedMessage)
@@ -363,7 +373,11 @@ impl Error {
             Kind::Parse(Parse::Header) => "invalid HTTP header parsed",
             Kind::Parse(Parse::TooLarge) => "message head is too large",
             Kind::Parse(Parse::Status) => "invalid HTTP status-code parsed",
+            #[cfg(all(feature = "http1", feature = "server"))]
+            Kind::Parse(Parse::Expect) => "unsupported HTTP expectation",
             Kind::IncompleteMessage => "connection closed before message completed",
+            #[cfg(all(feature = "http1", feature = "server", feature = "runtime"))]
+            Kind::HeaderTimeout => "read header from client timeout",
             #[cfg(feature = "http1")]
             Kind::UnexpectedMessage => "received unexpected message from connection",
             Kind::ChannelClosed => "channel closed",
@@ -0,0 +1,36 @@
+//! HTTP extensions
+
+use bytes::Bytes;
+use http::header::{GetAll, HeaderName, IntoHeaderName};
+use http::HeaderMap;
+
+/// A map from header names to their original casing as received in an HTTP message.
+///
+/// When a connection has `http1_preserve_header_case` set, this is stored
+/// in the extensions of each parsed message, with every spelling of a name
+/// in the order it was received. When a message carrying this map in its
+/// extensions is written out, the original casing is used for the header
+/// names it contains.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HeaderCaseMap(HeaderMap<Bytes>);
+
+impl HeaderCaseMap {
+    /// Returns a view of all spellings associated with that header name,
+    /// in the order they were found.
+    pub(crate) fn get_all<'a>(&'a self, name: &HeaderName) -> GetAll<'a, Bytes> {
+        self.0.get_all(name)
+    }
+
+    #[cfg(feature = "ffi")]
+    pub(crate) fn insert(&mut self, name: HeaderName, orig: Bytes) {
+        self.0.insert(name, orig);
+    }
+
+    /// Append a new header name casing.
+    pub(crate) fn append<N>(&mut self, name: N, orig: Bytes)
+    where
+        N: IntoHeaderName,
+    {
+        self.0.append(name, orig);
+    }
+}
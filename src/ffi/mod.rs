@@ -30,7 +30,7 @@ mod io;
 mod task;
 
 pub(crate) use self::body::UserBody;
-pub(crate) use self::http_types::{HeaderCaseMap, ReasonPhrase};
+pub(crate) use self::http_types::ReasonPhrase;
 
 pub const HYPER_ITER_CONTINUE: libc::c_int = 0;
 #[allow(unused)]
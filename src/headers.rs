@@ -82,6 +82,15 @@ pub(super) fn set_content_length_if_missing(headers: &mut HeaderMap, len: u64) {
         .or_insert_with(|| HeaderValue::from(len));
 }
 
+#[cfg(feature = "http1")]
+#[cfg(feature = "server")]
+pub(super) fn te_has_trailers(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(http::header::TE)
+        .iter()
+        .any(|value| connection_has(value, "trailers"))
+}
+
 #[cfg(feature = "http1")]
 pub(super) fn transfer_encoding_is_chunked(headers: &HeaderMap) -> bool {
     is_chunked(headers.get_all(http::header::TRANSFER_ENCODING).into_iter())
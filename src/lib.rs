@@ -92,6 +92,8 @@ pub mod upgrade;
 mod ffi;
 
 cfg_proto! {
+    #[cfg(feature = "http1")]
+    mod ext;
     mod headers;
     mod proto;
 }
@@ -1,17 +1,26 @@
 use std::fmt;
 use std::io;
 use std::marker::PhantomData;
+#[cfg(all(feature = "server", feature = "runtime"))]
+use std::time::Duration;
 
 use bytes::{Buf, Bytes};
 use http::header::{HeaderValue, CONNECTION};
 use http::{HeaderMap, Method, Version};
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(all(feature = "server", feature = "runtime"))]
+use tokio::time::Sleep;
 
 use super::io::Buffered;
+use super::role::HeadersBuf;
 use super::{Decoder, Encode, EncodedBuf, Encoder, Http1Transaction, ParseContext, Wants};
 use crate::body::DecodedLength;
+#[cfg(all(feature = "server", feature = "runtime"))]
+use crate::common::Future;
 use crate::common::{task, Pin, Poll, Unpin};
 use crate::headers::connection_keep_alive;
+#[cfg(feature = "server")]
+use crate::headers::te_has_trailers;
 use crate::proto::{BodyLength, MessageHead};
 
 const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
@@ -40,11 +49,18 @@ where
             io: Buffered::new(io),
             state: State {
                 allow_half_close: false,
+                #[cfg(feature = "server")]
+                allow_trailer_fields: false,
                 cached_headers: None,
                 error: None,
+                #[cfg(all(feature = "server", feature = "runtime"))]
+                h1_header_read_timeout: None,
+                #[cfg(all(feature = "server", feature = "runtime"))]
+                h1_header_read_timeout_fut: None,
+                h1_headers_buf: None,
                 keep_alive: KA::Busy,
                 method: None,
-                #[cfg(feature = "ffi")]
+                ignore_conflicting_content_length: false,
                 preserve_header_case: false,
                 title_case_headers: false,
                 notify_read: false,
@@ -68,6 +84,18 @@ where
         self.io.set_max_buf_size(max);
     }
 
+    pub(crate) fn set_http1_max_headers(&mut self, val: usize) {
+        self.state.h1_headers_buf = Some(HeadersBuf::new(val));
+    }
+
+    pub(crate) fn set_write_strategy_flatten(&mut self) {
+        self.io.set_write_strategy_flatten();
+    }
+
+    pub(crate) fn set_write_strategy_queue(&mut self) {
+        self.io.set_write_strategy_queue();
+    }
+
     #[cfg(feature = "client")]
     pub(crate) fn set_read_buf_exact_size(&mut self, sz: usize) {
         self.io.set_read_buf_exact_size(sz);
@@ -78,6 +106,20 @@ where
         self.state.title_case_headers = true;
     }
 
+    #[cfg(feature = "server")]
+    pub(crate) fn set_ignore_conflicting_content_length(&mut self) {
+        self.state.ignore_conflicting_content_length = true;
+    }
+
+    pub(crate) fn set_preserve_header_case(&mut self) {
+        self.state.preserve_header_case = true;
+    }
+
+    #[cfg(all(feature = "server", feature = "runtime"))]
+    pub(crate) fn set_http1_header_read_timeout(&mut self, val: Duration) {
+        self.state.h1_header_read_timeout = Some(val);
+    }
+
     #[cfg(feature = "server")]
     pub(crate) fn set_allow_half_close(&mut self) {
         self.state.allow_half_close = true;
@@ -139,19 +181,39 @@ where
         debug_assert!(self.can_read_head());
         trace!("Conn::read_head");
 
-        let msg = match ready!(self.io.parse::<T>(
+        let msg = match self.io.parse::<T>(
             cx,
             ParseContext {
                 cached_headers: &mut self.state.cached_headers,
                 req_method: &mut self.state.method,
-                #[cfg(feature = "ffi")]
+                h1_headers_buf: self.state.h1_headers_buf.as_mut(),
                 preserve_header_case: self.state.preserve_header_case,
+                ignore_conflicting_content_length: self.state.ignore_conflicting_content_length,
+            },
+        ) {
+            Poll::Ready(Ok(msg)) => msg,
+            Poll::Ready(Err(e)) => return self.on_read_head_error(e),
+            Poll::Pending => {
+                #[cfg(all(feature = "server", feature = "runtime"))]
+                {
+                    if self.poll_header_read_timeout(cx).is_ready() {
+                        debug!(
+                            "header read timeout ({:?}) elapsed",
+                            self.state.h1_header_read_timeout
+                        );
+                        return self.on_read_head_error(crate::Error::new_header_timeout());
+                    }
+                }
+                return Poll::Pending;
             }
-        )) {
-            Ok(msg) => msg,
-            Err(e) => return self.on_read_head_error(e),
         };
 
+        #[cfg(all(feature = "server", feature = "runtime"))]
+        {
+            // The full head has arrived, so reset the deadline for the next one.
+            self.state.h1_header_read_timeout_fut = None;
+        }
+
         // Note: don't deconstruct `msg` into local variables, it appears
         // the optimizer doesn't remove the extra copies.
 
@@ -160,6 +222,12 @@ where
         self.state.busy();
         self.state.keep_alive &= msg.keep_alive;
         self.state.version = msg.head.version;
+        #[cfg(feature = "server")]
+        {
+            if T::should_read_first() {
+                self.state.allow_trailer_fields = te_has_trailers(&msg.head.headers);
+            }
+        }
 
         let mut wants = if msg.wants_upgrade {
             Wants::UPGRADE
@@ -185,6 +253,22 @@ where
         Poll::Ready(Some(Ok((msg.head, msg.decode, wants))))
     }
 
+    #[cfg(all(feature = "server", feature = "runtime"))]
+    fn poll_header_read_timeout(&mut self, cx: &mut task::Context<'_>) -> Poll<()> {
+        let dur = match self.state.h1_header_read_timeout {
+            Some(dur) => dur,
+            None => return Poll::Pending,
+        };
+
+        // The deadline starts the first time we have to wait on the
+        // transport for a message head.
+        self.state
+            .h1_header_read_timeout_fut
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(dur)))
+            .as_mut()
+            .poll(cx)
+    }
+
     fn on_read_head_error<Z>(&mut self, e: crate::Error) -> Poll<Option<crate::Result<Z>>> {
         // If we are currently waiting on a message, then an empty
         // message should be reported as an error. If not, it is just
@@ -484,7 +568,7 @@ where
         {
             if T::is_client() && !self.state.preserve_header_case {
                 self.state.preserve_header_case =
-                    head.extensions.get::<crate::ffi::HeaderCaseMap>().is_some();
+                    head.extensions.get::<crate::ext::HeaderCaseMap>().is_some();
             }
         }
 
@@ -495,6 +579,8 @@ where
                 body,
                 #[cfg(feature = "server")]
                 keep_alive: self.state.wants_keep_alive(),
+                #[cfg(feature = "server")]
+                allow_trailer_fields: self.state.allow_trailer_fields,
                 req_method: &mut self.state.method,
                 title_case_headers: self.state.title_case_headers,
             },
@@ -600,6 +686,33 @@ where
         self.state.writing = state;
     }
 
+    pub(crate) fn wants_trailers(&self) -> bool {
+        match self.state.writing {
+            Writing::Body(ref encoder) => encoder.wants_trailers(),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn write_trailers(&mut self, trailers: HeaderMap) {
+        debug_assert!(self.can_write_body() && self.can_buffer_body());
+
+        let state = match self.state.writing {
+            Writing::Body(ref encoder) => {
+                if let Some(enc_buf) = encoder.encode_trailers(trailers) {
+                    self.io.buffer(enc_buf);
+                }
+                if encoder.is_last() {
+                    Writing::Closed
+                } else {
+                    Writing::KeepAlive
+                }
+            }
+            _ => unreachable!("write_trailers invalid state: {:?}", self.state.writing),
+        };
+
+        self.state.writing = state;
+    }
+
     pub(crate) fn end_body(&mut self) -> crate::Result<()> {
         debug_assert!(self.can_write_body());
 
@@ -738,11 +851,27 @@ impl<I: Unpin, B, T> Unpin for Conn<I, B, T> {}
 
 struct State {
     allow_half_close: bool,
+    /// Whether the current request accepts trailer fields in the response,
+    /// with `TE: trailers`.
+    #[cfg(feature = "server")]
+    allow_trailer_fields: bool,
     /// Re-usable HeaderMap to reduce allocating new ones.
     cached_headers: Option<HeaderMap>,
     /// If an error occurs when there wasn't a direct way to return it
     /// back to the user, this is set.
     error: Option<crate::Error>,
+    /// Maximum time allowed for a full message head to arrive.
+    #[cfg(all(feature = "server", feature = "runtime"))]
+    h1_header_read_timeout: Option<Duration>,
+    /// The pending deadline for the message head currently being read.
+    #[cfg(all(feature = "server", feature = "runtime"))]
+    h1_header_read_timeout_fut: Option<Pin<Box<Sleep>>>,
+    /// If set, the slots for a custom maximum number of headers allowed in
+    /// a message head, overriding the default of 100.
+    h1_headers_buf: Option<HeadersBuf>,
+    /// Whether a request with both Transfer-Encoding and Content-Length is
+    /// framed by its Transfer-Encoding, instead of being rejected.
+    ignore_conflicting_content_length: bool,
     /// Current keep-alive status.
     keep_alive: KA,
     /// If mid-message, the HTTP Method that started it.
@@ -750,7 +879,6 @@ struct State {
     /// This is used to know things such as if the message can include
     /// a body or not.
     method: Option<Method>,
-    #[cfg(feature = "ffi")]
     preserve_header_case: bool,
     title_case_headers: bool,
     /// Set to true when the Dispatcher should poll read operations
@@ -17,6 +17,9 @@ pub(crate) struct Dispatcher<D, Bs: HttpBody, I, T> {
     dispatch: D,
     body_tx: Option<crate::body::Sender>,
     body_rx: Pin<Box<Option<Bs>>>,
+    /// Whether `poll_data` on the current body has returned `None`, and
+    /// only its trailers are left to poll.
+    is_body_data_done: bool,
     is_closing: bool,
 }
 
@@ -72,6 +75,7 @@ where
             dispatch,
             body_tx: None,
             body_rx: Box::pin(None),
+            is_body_data_done: false,
             is_closing: false,
         }
     }
@@ -312,6 +316,7 @@ where
                             .map(BodyLength::Known)
                             .or_else(|| Some(BodyLength::Unknown));
                         self.body_rx.set(Some(body));
+                        self.is_body_data_done = false;
                         btype
                     };
                     self.conn.write_head(head, body_type);
@@ -336,7 +341,13 @@ where
                         continue;
                     }
 
-                    let item = ready!(body.as_mut().poll_data(cx));
+                    // Bodies aren't required to be fused, so once the data
+                    // is done, only the trailers are polled.
+                    let item = if self.is_body_data_done {
+                        None
+                    } else {
+                        ready!(body.as_mut().poll_data(cx))
+                    };
                     if let Some(item) = item {
                         let chunk = item.map_err(|e| {
                             *clear_body = true;
@@ -359,6 +370,19 @@ where
                             self.conn.write_body(chunk);
                         }
                     } else {
+                        if self.conn.wants_trailers() {
+                            self.is_body_data_done = true;
+                            let trailers =
+                                ready!(body.as_mut().poll_trailers(cx)).map_err(|e| {
+                                    *clear_body = true;
+                                    crate::Error::new_user_body(e)
+                                })?;
+                            if let Some(trailers) = trailers {
+                                *clear_body = true;
+                                self.conn.write_trailers(trailers);
+                                continue;
+                            }
+                        }
                         *clear_body = true;
                         self.conn.end_body()?;
                     }
@@ -2,7 +2,9 @@ use std::fmt;
 use std::io::IoSlice;
 
 use bytes::buf::{Chain, Take};
-use bytes::Buf;
+use bytes::{Buf, Bytes};
+use http::header::{self, HeaderName, HeaderValue};
+use http::HeaderMap;
 
 use super::io::WriteBuf;
 
@@ -13,6 +15,10 @@ type StaticBuf = &'static [u8];
 pub(crate) struct Encoder {
     kind: Kind,
     is_last: bool,
+    /// The values of any `Trailer` headers declared in the message head.
+    ///
+    /// Trailers are only written if they were declared up front.
+    allowed_trailer_fields: Option<Vec<HeaderValue>>,
 }
 
 #[derive(Debug)]
@@ -45,6 +51,7 @@ enum BufKind<B> {
     Limited(Take<B>),
     Chunked(Chain<Chain<ChunkSize, B>, StaticBuf>),
     ChunkedEnd(StaticBuf),
+    Trailers(Bytes),
 }
 
 impl Encoder {
@@ -52,6 +59,7 @@ impl Encoder {
         Encoder {
             kind,
             is_last: false,
+            allowed_trailer_fields: None,
         }
     }
     pub(crate) fn chunked() -> Encoder {
@@ -67,6 +75,17 @@ impl Encoder {
         Encoder::new(Kind::CloseDelimited)
     }
 
+    #[cfg(feature = "server")]
+    pub(crate) fn with_trailer_fields(self, trailers: Vec<HeaderValue>) -> Encoder {
+        match self.kind {
+            Kind::Chunked => Encoder {
+                allowed_trailer_fields: Some(trailers),
+                ..self
+            },
+            _ => self,
+        }
+    }
+
     pub(crate) fn is_eof(&self) -> bool {
         matches!(self.kind, Kind::Length(0))
     }
@@ -101,6 +120,41 @@ impl Encoder {
         }
     }
 
+    pub(crate) fn wants_trailers(&self) -> bool {
+        self.kind == Kind::Chunked && self.allowed_trailer_fields.is_some()
+    }
+
+    /// Encodes the terminating chunk followed by any declared trailer fields.
+    ///
+    /// Returns `None` if this encoder cannot send trailers.
+    pub(crate) fn encode_trailers<B>(&self, trailers: HeaderMap) -> Option<EncodedBuf<B>> {
+        let allowed = match (&self.kind, &self.allowed_trailer_fields) {
+            (Kind::Chunked, Some(ref allowed)) => allowed,
+            _ => return None,
+        };
+
+        let mut buf = Vec::from(&b"0\r\n"[..]);
+        for (name, value) in trailers.iter() {
+            if !is_declared_trailer_field(allowed, name) {
+                trace!("ignoring undeclared trailer field: {:?}", name);
+                continue;
+            }
+            if !is_valid_trailer_field(name) {
+                trace!("ignoring forbidden trailer field: {:?}", name);
+                continue;
+            }
+            buf.extend_from_slice(name.as_str().as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        buf.extend_from_slice(b"\r\n");
+
+        Some(EncodedBuf {
+            kind: BufKind::Trailers(Bytes::from(buf)),
+        })
+    }
+
     pub(crate) fn encode<B>(&mut self, msg: B) -> EncodedBuf<B>
     where
         B: Buf,
@@ -225,6 +279,7 @@ where
             BufKind::Limited(ref b) => b.remaining(),
             BufKind::Chunked(ref b) => b.remaining(),
             BufKind::ChunkedEnd(ref b) => b.remaining(),
+            BufKind::Trailers(ref b) => b.remaining(),
         }
     }
 
@@ -235,6 +290,7 @@ where
             BufKind::Limited(ref b) => b.chunk(),
             BufKind::Chunked(ref b) => b.chunk(),
             BufKind::ChunkedEnd(ref b) => b.chunk(),
+            BufKind::Trailers(ref b) => b.chunk(),
         }
     }
 
@@ -245,6 +301,7 @@ where
             BufKind::Limited(ref mut b) => b.advance(cnt),
             BufKind::Chunked(ref mut b) => b.advance(cnt),
             BufKind::ChunkedEnd(ref mut b) => b.advance(cnt),
+            BufKind::Trailers(ref mut b) => b.advance(cnt),
         }
     }
 
@@ -255,10 +312,39 @@ where
             BufKind::Limited(ref b) => b.chunks_vectored(dst),
             BufKind::Chunked(ref b) => b.chunks_vectored(dst),
             BufKind::ChunkedEnd(ref b) => b.chunks_vectored(dst),
+            BufKind::Trailers(ref b) => b.chunks_vectored(dst),
         }
     }
 }
 
+fn is_declared_trailer_field(allowed: &[HeaderValue], name: &HeaderName) -> bool {
+    allowed
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|field| field.trim().eq_ignore_ascii_case(name.as_str()))
+}
+
+// Fields that control framing, routing or payload processing must not be
+// sent as trailers (RFC 7230, Section 4.1.2).
+fn is_valid_trailer_field(name: &HeaderName) -> bool {
+    !matches!(
+        *name,
+        header::AUTHORIZATION
+            | header::CACHE_CONTROL
+            | header::CONTENT_ENCODING
+            | header::CONTENT_LENGTH
+            | header::CONTENT_RANGE
+            | header::CONTENT_TYPE
+            | header::HOST
+            | header::MAX_FORWARDS
+            | header::SET_COOKIE
+            | header::TE
+            | header::TRAILER
+            | header::TRANSFER_ENCODING
+    )
+}
+
 #[cfg(target_pointer_width = "32")]
 const USIZE_BYTES: usize = 4;
 
@@ -353,6 +439,8 @@ impl<B: Buf> From<Chain<Chain<ChunkSize, B>, StaticBuf>> for EncodedBuf<B> {
 #[cfg(test)]
 mod tests {
     use bytes::BufMut;
+    use http::header::HeaderValue;
+    use http::HeaderMap;
 
     use super::super::io::Cursor;
     use super::Encoder;
@@ -382,6 +470,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn chunked_with_trailers() {
+        let declared = HeaderValue::from_static("chunky-trailer, Content-Length");
+        let encoder = Encoder::chunked().with_trailer_fields(vec![declared]);
+        assert!(encoder.wants_trailers());
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert("chunky-trailer", HeaderValue::from_static("header data"));
+        trailers.insert("undeclared", HeaderValue::from_static("nope"));
+        trailers.insert("content-length", HeaderValue::from_static("10"));
+
+        let mut dst = Vec::new();
+        let end = encoder
+            .encode_trailers::<Cursor<Vec<u8>>>(trailers)
+            .unwrap();
+        dst.put(end);
+
+        assert_eq!(dst, b"0\r\nchunky-trailer: header data\r\n\r\n".as_ref());
+    }
+
+    #[test]
+    fn chunked_without_declared_trailers() {
+        let encoder = Encoder::chunked();
+        assert!(!encoder.wants_trailers());
+        assert!(encoder
+            .encode_trailers::<Cursor<Vec<u8>>>(HeaderMap::new())
+            .is_none());
+    }
+
     #[test]
     fn length() {
         let max_len = 8;
@@ -91,7 +91,6 @@ where
         self.read_buf_strategy = ReadStrategy::Exact(sz);
     }
 
-    #[cfg(feature = "server")]
     pub(crate) fn set_write_strategy_flatten(&mut self) {
         // this should always be called only at construction time,
         // so this assert is here to catch myself
@@ -99,6 +98,13 @@ where
         self.write_buf.set_strategy(WriteStrategy::Flatten);
     }
 
+    pub(crate) fn set_write_strategy_queue(&mut self) {
+        // this should always be called only at construction time,
+        // so this assert is here to catch myself
+        debug_assert!(self.write_buf.queue.bufs_cnt() == 0);
+        self.write_buf.set_strategy(WriteStrategy::Queue);
+    }
+
     pub(crate) fn read_buf(&self) -> &[u8] {
         self.read_buf.as_ref()
     }
@@ -148,7 +154,7 @@ where
     pub(super) fn parse<S>(
         &mut self,
         cx: &mut task::Context<'_>,
-        parse_ctx: ParseContext<'_>,
+        mut parse_ctx: ParseContext<'_>,
     ) -> Poll<crate::Result<ParsedMessage<S::Incoming>>>
     where
         S: Http1Transaction,
@@ -159,8 +165,9 @@ where
                 ParseContext {
                     cached_headers: parse_ctx.cached_headers,
                     req_method: parse_ctx.req_method,
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: parse_ctx.h1_headers_buf.as_deref_mut(),
                     preserve_header_case: parse_ctx.preserve_header_case,
+                    ignore_conflicting_content_length: parse_ctx.ignore_conflicting_content_length,
                 },
             )? {
                 Some(msg) => {
@@ -478,7 +485,6 @@ impl<B> WriteBuf<B>
 where
     B: Buf,
 {
-    #[cfg(feature = "server")]
     fn set_strategy(&mut self, strategy: WriteStrategy) {
         self.strategy = strategy;
     }
@@ -638,8 +644,9 @@ mod tests {
             let parse_ctx = ParseContext {
                 cached_headers: &mut None,
                 req_method: &mut None,
-                #[cfg(feature = "ffi")]
+                h1_headers_buf: None,
                 preserve_header_case: false,
+                ignore_conflicting_content_length: false,
             };
             assert!(buffered
                 .parse::<ClientTransaction>(cx, parse_ctx)
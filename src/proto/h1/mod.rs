@@ -70,8 +70,9 @@ pub(crate) struct ParsedMessage<T> {
 pub(crate) struct ParseContext<'a> {
     cached_headers: &'a mut Option<HeaderMap>,
     req_method: &'a mut Option<Method>,
-    #[cfg(feature = "ffi")]
+    h1_headers_buf: Option<&'a mut role::HeadersBuf>,
     preserve_header_case: bool,
+    ignore_conflicting_content_length: bool,
 }
 
 /// Passed to Http1Transaction::encode
@@ -80,6 +81,8 @@ pub(crate) struct Encode<'a, T> {
     body: Option<BodyLength>,
     #[cfg(feature = "server")]
     keep_alive: bool,
+    #[cfg(feature = "server")]
+    allow_trailer_fields: bool,
     req_method: &'a mut Option<Method>,
     title_case_headers: bool,
 }
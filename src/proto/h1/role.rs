@@ -4,6 +4,7 @@
 
 use std::fmt::{self, Write};
 use std::mem;
+use std::slice;
 
 #[cfg(feature = "ffi")]
 use bytes::Bytes;
@@ -15,6 +16,7 @@ use crate::body::DecodedLength;
 #[cfg(feature = "server")]
 use crate::common::date;
 use crate::error::Parse;
+use crate::ext::HeaderCaseMap;
 use crate::headers;
 use crate::proto::h1::{
     Encode, Encoder, Http1Transaction, ParseContext, ParseResult, ParsedMessage,
@@ -123,17 +125,41 @@ impl Http1Transaction for Server {
         // but we *never* read any of it until after httparse has assigned
         // values into it. By not zeroing out the stack memory, this saves
         // a good ~5% on pipeline benchmarks.
-        let mut headers_indices: [HeaderIndices; MAX_HEADERS] = unsafe { mem::uninitialized() };
+        //
+        // A custom maximum can't use the stack, so those parse into the
+        // slots kept on the connection instead.
+        let mut headers_indices_array: [HeaderIndices; MAX_HEADERS];
+        let mut headers_array: [httparse::Header<'_>; MAX_HEADERS];
+        let headers_indices: &mut [HeaderIndices];
         {
-            let mut headers: [httparse::Header<'_>; MAX_HEADERS] = unsafe { mem::uninitialized() };
-            trace!(
-                "Request.parse([Header; {}], [u8; {}])",
-                headers.len(),
-                buf.len()
-            );
-            let mut req = httparse::Request::new(&mut headers);
             let bytes = buf.as_ref();
-            match req.parse(bytes) {
+            let mut req = httparse::Request::new(&mut []);
+            let parsed = match ctx.h1_headers_buf {
+                Some(HeadersBuf { indices, headers }) => {
+                    let headers = HeadersBuf::headers(headers);
+                    trace!(
+                        "Request.parse([Header; {}], [u8; {}])",
+                        headers.len(),
+                        bytes.len()
+                    );
+                    headers_indices = indices;
+                    httparse::ParserConfig::default()
+                        .parse_request_with_uninit_headers(&mut req, bytes, headers)
+                }
+                None => {
+                    headers_indices_array = unsafe { mem::uninitialized() };
+                    headers_array = unsafe { mem::uninitialized() };
+                    trace!(
+                        "Request.parse([Header; {}], [u8; {}])",
+                        headers_array.len(),
+                        bytes.len()
+                    );
+                    headers_indices = &mut headers_indices_array;
+                    req = httparse::Request::new(&mut headers_array);
+                    req.parse(bytes)
+                }
+            };
+            match parsed {
                 Ok(httparse::Status::Complete(parsed_len)) => {
                     trace!("Request.parse Complete({})", parsed_len);
                     len = parsed_len;
@@ -152,7 +178,7 @@ impl Http1Transaction for Server {
                     };
                     trace!("headers: {:?}", &req.headers);
 
-                    record_header_indices(bytes, &req.headers, &mut headers_indices)?;
+                    record_header_indices(bytes, &req.headers, headers_indices)?;
                     headers_len = req.headers.len();
                 }
                 Ok(httparse::Status::Partial) => return Ok(None),
@@ -195,6 +221,12 @@ impl Http1Transaction for Server {
 
         headers.reserve(headers_len);
 
+        let mut header_case_map = if ctx.preserve_header_case {
+            Some(HeaderCaseMap::default())
+        } else {
+            None
+        };
+
         for header in &headers_indices[..headers_len] {
             let name = header_name!(&slice[header.name.0..header.name.1]);
             let value = header_value!(slice.slice(header.value.0..header.value.1));
@@ -209,6 +241,14 @@ impl Http1Transaction for Server {
                         debug!("HTTP/1.0 cannot have Transfer-Encoding header");
                         return Err(Parse::Header);
                     }
+                    // A message with both Transfer-Encoding and Content-Length
+                    // could be framed differently by an intermediary, which
+                    // allows request smuggling, so it is rejected unless the
+                    // Content-Length has been configured to be ignored.
+                    if con_len.is_some() && !ctx.ignore_conflicting_content_length {
+                        debug!("request with both transfer-encoding and content-length");
+                        return Err(Parse::Header);
+                    }
                     is_te = true;
                     if headers::is_chunked_(&value) {
                         is_te_chunked = true;
@@ -217,7 +257,11 @@ impl Http1Transaction for Server {
                 }
                 header::CONTENT_LENGTH => {
                     if is_te {
-                        continue;
+                        if ctx.ignore_conflicting_content_length {
+                            continue;
+                        }
+                        debug!("request with both transfer-encoding and content-length");
+                        return Err(Parse::Header);
                     }
                     let len = value
                         .to_str()
@@ -248,7 +292,14 @@ impl Http1Transaction for Server {
                     }
                 }
                 header::EXPECT => {
-                    expect_continue = value.as_bytes() == b"100-continue";
+                    // 100-continue is the only expectation defined by
+                    // RFC 7231, anything else must be answered with 417.
+                    if value.as_bytes().eq_ignore_ascii_case(b"100-continue") {
+                        expect_continue = true;
+                    } else {
+                        debug!("request with unsupported expectation: {:?}", value);
+                        return Err(Parse::Expect);
+                    }
                 }
                 header::UPGRADE => {
                     // Upgrades are only allowed with HTTP/1.1
@@ -258,6 +309,10 @@ impl Http1Transaction for Server {
                 _ => (),
             }
 
+            if let Some(ref mut header_case_map) = header_case_map {
+                header_case_map.append(&name, slice.slice(header.name.0..header.name.1));
+            }
+
             headers.append(name, value);
         }
 
@@ -266,6 +321,12 @@ impl Http1Transaction for Server {
             return Err(Parse::Header);
         }
 
+        let mut extensions = http::Extensions::default();
+
+        if let Some(header_case_map) = header_case_map {
+            extensions.insert(header_case_map);
+        }
+
         *ctx.req_method = Some(subject.0.clone());
 
         Ok(Some(ParsedMessage {
@@ -273,7 +334,7 @@ impl Http1Transaction for Server {
                 version,
                 subject,
                 headers,
-                extensions: http::Extensions::default(),
+                extensions,
             },
             decode: decoder,
             expect_continue,
@@ -363,6 +424,9 @@ impl Http1Transaction for Server {
         let mut is_name_written = false;
         let mut must_write_chunked = false;
         let mut prev_con_len = None;
+        let mut allowed_trailer_fields: Option<Vec<HeaderValue>> = None;
+        let orig_headers = msg.head.extensions.get::<HeaderCaseMap>();
+        let mut orig_names = None;
 
         macro_rules! handle_is_name_written {
             () => {{
@@ -385,11 +449,16 @@ impl Http1Transaction for Server {
 
         'headers: for (opt_name, value) in msg.head.headers.drain() {
             if let Some(n) = opt_name {
+                orig_names = orig_headers.map(|orig| orig.get_all(&n).into_iter());
                 cur_name = Some(n);
                 handle_is_name_written!();
                 is_name_written = false;
             }
             let name = cur_name.as_ref().expect("current header name");
+            let orig_name = orig_names
+                .as_mut()
+                .and_then(|names| names.next())
+                .map(|orig| &orig[..]);
             match *name {
                 header::CONTENT_LENGTH => {
                     if wrote_len && !is_name_written {
@@ -420,7 +489,8 @@ impl Http1Transaction for Server {
 
                             if !is_name_written {
                                 encoder = Encoder::length(known_len);
-                                extend(dst, b"content-length: ");
+                                extend_header_name(dst, name, orig_name);
+                                extend(dst, b": ");
                                 extend(dst, value.as_bytes());
                                 wrote_len = true;
                                 is_name_written = true;
@@ -448,7 +518,8 @@ impl Http1Transaction for Server {
                                 } else {
                                     // we haven't written content-length yet!
                                     encoder = Encoder::length(len);
-                                    extend(dst, b"content-length: ");
+                                    extend_header_name(dst, name, orig_name);
+                                    extend(dst, b": ");
                                     extend(dst, value.as_bytes());
                                     wrote_len = true;
                                     is_name_written = true;
@@ -503,7 +574,8 @@ impl Http1Transaction for Server {
                     if !is_name_written {
                         encoder = Encoder::chunked();
                         is_name_written = true;
-                        extend(dst, b"transfer-encoding: ");
+                        extend_header_name(dst, name, orig_name);
+                        extend(dst, b": ");
                         extend(dst, value.as_bytes());
                     } else {
                         extend(dst, b", ");
@@ -517,7 +589,8 @@ impl Http1Transaction for Server {
                     }
                     if !is_name_written {
                         is_name_written = true;
-                        extend(dst, b"connection: ");
+                        extend_header_name(dst, name, orig_name);
+                        extend(dst, b": ");
                         extend(dst, value.as_bytes());
                     } else {
                         extend(dst, b", ");
@@ -528,6 +601,11 @@ impl Http1Transaction for Server {
                 header::DATE => {
                     wrote_date = true;
                 }
+                header::TRAILER => {
+                    allowed_trailer_fields
+                        .get_or_insert_with(Vec::new)
+                        .push(value.clone());
+                }
                 _ => (),
             }
             //TODO: this should perhaps instead combine them into
@@ -539,7 +617,7 @@ impl Http1Transaction for Server {
                 "{:?} set is_name_written and didn't continue loop",
                 name,
             );
-            extend(dst, name.as_str().as_bytes());
+            extend_header_name(dst, name, orig_name);
             extend(dst, b": ");
             extend(dst, value.as_bytes());
             extend(dst, b"\r\n");
@@ -587,6 +665,14 @@ impl Http1Transaction for Server {
             encoder = Encoder::length(0);
         }
 
+        // Trailer fields are only sent if the request said it accepts
+        // them, with `TE: trailers`.
+        if msg.allow_trailer_fields {
+            if let Some(allowed_trailer_fields) = allowed_trailer_fields {
+                encoder = encoder.with_trailer_fields(allowed_trailer_fields);
+            }
+        }
+
         // cached date is much faster than formatting every request
         if !wrote_date {
             dst.reserve(date::DATE_VALUE_LENGTH + 8);
@@ -608,6 +694,9 @@ impl Http1Transaction for Server {
             | Kind::Parse(Parse::Uri)
             | Kind::Parse(Parse::Version) => StatusCode::BAD_REQUEST,
             Kind::Parse(Parse::TooLarge) => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            Kind::Parse(Parse::Expect) => StatusCode::EXPECTATION_FAILED,
+            #[cfg(feature = "runtime")]
+            Kind::HeaderTimeout => StatusCode::REQUEST_TIMEOUT,
             _ => return None,
         };
 
@@ -655,24 +744,44 @@ impl Http1Transaction for Client {
     type Outgoing = RequestLine;
     const LOG: &'static str = "{role=client}";
 
-    fn parse(buf: &mut BytesMut, ctx: ParseContext<'_>) -> ParseResult<StatusCode> {
+    fn parse(buf: &mut BytesMut, mut ctx: ParseContext<'_>) -> ParseResult<StatusCode> {
         debug_assert!(!buf.is_empty(), "parse called with empty buf");
 
         // Loop to skip information status code headers (100 Continue, etc).
         loop {
             // Unsafe: see comment in Server Http1Transaction, above.
-            let mut headers_indices: [HeaderIndices; MAX_HEADERS] = unsafe { mem::uninitialized() };
+            let mut headers_indices_array: [HeaderIndices; MAX_HEADERS];
+            let mut headers_array: [httparse::Header<'_>; MAX_HEADERS];
+            let headers_indices: &mut [HeaderIndices];
             let (len, status, reason, version, headers_len) = {
-                let mut headers: [httparse::Header<'_>; MAX_HEADERS] =
-                    unsafe { mem::uninitialized() };
-                trace!(
-                    "Response.parse([Header; {}], [u8; {}])",
-                    headers.len(),
-                    buf.len()
-                );
-                let mut res = httparse::Response::new(&mut headers);
                 let bytes = buf.as_ref();
-                match res.parse(bytes)? {
+                let mut res = httparse::Response::new(&mut []);
+                let parsed = match ctx.h1_headers_buf.as_deref_mut() {
+                    Some(HeadersBuf { indices, headers }) => {
+                        let headers = HeadersBuf::headers(headers);
+                        trace!(
+                            "Response.parse([Header; {}], [u8; {}])",
+                            headers.len(),
+                            bytes.len()
+                        );
+                        headers_indices = indices;
+                        httparse::ParserConfig::default()
+                            .parse_response_with_uninit_headers(&mut res, bytes, headers)
+                    }
+                    None => {
+                        headers_indices_array = unsafe { mem::uninitialized() };
+                        headers_array = unsafe { mem::uninitialized() };
+                        trace!(
+                            "Response.parse([Header; {}], [u8; {}])",
+                            headers_array.len(),
+                            bytes.len()
+                        );
+                        headers_indices = &mut headers_indices_array;
+                        res = httparse::Response::new(&mut headers_array);
+                        res.parse(bytes)
+                    }
+                };
+                match parsed? {
                     httparse::Status::Complete(len) => {
                         trace!("Response.parse Complete({})", len);
                         let status = StatusCode::from_u16(res.code.unwrap())?;
@@ -695,7 +804,7 @@ impl Http1Transaction for Client {
                         } else {
                             Version::HTTP_10
                         };
-                        record_header_indices(bytes, &res.headers, &mut headers_indices)?;
+                        record_header_indices(bytes, &res.headers, headers_indices)?;
                         let headers_len = res.headers.len();
                         (len, status, reason, version, headers_len)
                     }
@@ -709,8 +818,11 @@ impl Http1Transaction for Client {
 
             let mut keep_alive = version == Version::HTTP_11;
 
-            #[cfg(feature = "ffi")]
-            let mut header_case_map = crate::ffi::HeaderCaseMap::default();
+            let mut header_case_map = if ctx.preserve_header_case {
+                Some(HeaderCaseMap::default())
+            } else {
+                None
+            };
 
             headers.reserve(headers_len);
             for header in &headers_indices[..headers_len] {
@@ -728,19 +840,16 @@ impl Http1Transaction for Client {
                     }
                 }
 
-                #[cfg(feature = "ffi")]
-                if ctx.preserve_header_case {
+                if let Some(ref mut header_case_map) = header_case_map {
                     header_case_map.append(&name, slice.slice(header.name.0..header.name.1));
                 }
 
                 headers.append(name, value);
             }
 
-            #[allow(unused_mut)]
             let mut extensions = http::Extensions::default();
 
-            #[cfg(feature = "ffi")]
-            if ctx.preserve_header_case {
+            if let Some(header_case_map) = header_case_map {
                 extensions.insert(header_case_map);
             }
 
@@ -807,26 +916,12 @@ impl Http1Transaction for Client {
         }
         extend(dst, b"\r\n");
 
-        #[cfg(feature = "ffi")]
-        {
-            if msg.title_case_headers {
-                write_headers_title_case(&msg.head.headers, dst);
-            } else if let Some(orig_headers) =
-                msg.head.extensions.get::<crate::ffi::HeaderCaseMap>()
-            {
-                write_headers_original_case(&msg.head.headers, orig_headers, dst);
-            } else {
-                write_headers(&msg.head.headers, dst);
-            }
-        }
-
-        #[cfg(not(feature = "ffi"))]
-        {
-            if msg.title_case_headers {
-                write_headers_title_case(&msg.head.headers, dst);
-            } else {
-                write_headers(&msg.head.headers, dst);
-            }
+        if msg.title_case_headers {
+            write_headers_title_case(&msg.head.headers, dst);
+        } else if let Some(orig_headers) = msg.head.extensions.get::<HeaderCaseMap>() {
+            write_headers_original_case(&msg.head.headers, orig_headers, dst);
+        } else {
+            write_headers(&msg.head.headers, dst);
         }
 
         extend(dst, b"\r\n");
@@ -1065,6 +1160,56 @@ struct HeaderIndices {
     value: (usize, usize),
 }
 
+impl HeaderIndices {
+    const EMPTY: HeaderIndices = HeaderIndices {
+        name: (0, 0),
+        value: (0, 0),
+    };
+}
+
+/// Header slots for a connection with a custom maximum number of headers.
+///
+/// These are allocated once per connection and reused for every parse
+/// attempt, instead of allocating `max` slots each time more of a message
+/// head has been read.
+pub(crate) struct HeadersBuf {
+    indices: Vec<HeaderIndices>,
+    headers: Vec<mem::MaybeUninit<httparse::Header<'static>>>,
+}
+
+impl HeadersBuf {
+    pub(crate) fn new(max: usize) -> HeadersBuf {
+        HeadersBuf {
+            indices: vec![HeaderIndices::EMPTY; max],
+            headers: (0..max).map(|_| mem::MaybeUninit::uninit()).collect(),
+        }
+    }
+
+    fn headers<'a, 'b>(
+        headers: &'a mut Vec<mem::MaybeUninit<httparse::Header<'static>>>,
+    ) -> &'a mut [mem::MaybeUninit<httparse::Header<'b>>] {
+        // Unsafe: only the lifetime of the borrowed header slices changes.
+        // The slots are never assumed to be initialized, so headers left
+        // over from an earlier parse are never read.
+        unsafe {
+            slice::from_raw_parts_mut(
+                headers
+                    .as_mut_ptr()
+                    .cast::<mem::MaybeUninit<httparse::Header<'b>>>(),
+                headers.len(),
+            )
+        }
+    }
+}
+
+impl fmt::Debug for HeadersBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeadersBuf")
+            .field("max", &self.indices.len())
+            .finish()
+    }
+}
+
 fn record_header_indices(
     bytes: &[u8],
     headers: &[httparse::Header<'_>],
@@ -1139,13 +1284,9 @@ fn write_headers(headers: &HeaderMap, dst: &mut Vec<u8>) {
     }
 }
 
-#[cfg(feature = "ffi")]
+#[cfg(feature = "client")]
 #[cold]
-fn write_headers_original_case(
-    headers: &HeaderMap,
-    orig_case: &crate::ffi::HeaderCaseMap,
-    dst: &mut Vec<u8>,
-) {
+fn write_headers_original_case(headers: &HeaderMap, orig_case: &HeaderCaseMap, dst: &mut Vec<u8>) {
     // For each header name/value pair, there may be a value in the casemap
     // that corresponds to the HeaderValue. So, we iterator all the keys,
     // and for each one, try to pair the originally cased name with the value.
@@ -1173,6 +1314,16 @@ fn write_headers_original_case(
     }
 }
 
+/// Writes a header name, using its original casing if one was recorded.
+#[cfg(feature = "server")]
+#[inline]
+fn extend_header_name(dst: &mut Vec<u8>, name: &HeaderName, orig_name: Option<&[u8]>) {
+    match orig_name {
+        Some(orig_name) => extend(dst, orig_name),
+        None => extend(dst, name.as_str().as_bytes()),
+    }
+}
+
 struct FastWrite<'a>(&'a mut Vec<u8>);
 
 impl<'a> fmt::Write for FastWrite<'a> {
@@ -1195,7 +1346,7 @@ fn extend(dst: &mut Vec<u8>, data: &[u8]) {
 
 #[cfg(test)]
 mod tests {
-    use bytes::BytesMut;
+    use bytes::{Bytes, BytesMut};
 
     use super::*;
 
@@ -1209,8 +1360,9 @@ mod tests {
             ParseContext {
                 cached_headers: &mut None,
                 req_method: &mut method,
-                #[cfg(feature = "ffi")]
+                h1_headers_buf: None,
                 preserve_header_case: false,
+                ignore_conflicting_content_length: false,
             },
         )
         .unwrap()
@@ -1231,8 +1383,9 @@ mod tests {
         let ctx = ParseContext {
             cached_headers: &mut None,
             req_method: &mut Some(crate::Method::GET),
-            #[cfg(feature = "ffi")]
+            h1_headers_buf: None,
             preserve_header_case: false,
+            ignore_conflicting_content_length: false,
         };
         let msg = Client::parse(&mut raw, ctx).unwrap().unwrap();
         assert_eq!(raw.len(), 0);
@@ -1248,12 +1401,56 @@ mod tests {
         let ctx = ParseContext {
             cached_headers: &mut None,
             req_method: &mut None,
-            #[cfg(feature = "ffi")]
+            h1_headers_buf: None,
             preserve_header_case: false,
+            ignore_conflicting_content_length: false,
         };
         Server::parse(&mut raw, ctx).unwrap_err();
     }
 
+    #[test]
+    fn test_parse_max_headers() {
+        let _ = pretty_env_logger::try_init();
+        let mut many = String::from("GET / HTTP/1.1\r\n");
+        for i in 0..150 {
+            many.push_str(&format!("x-header-{}: {}\r\n", i, i));
+        }
+        many.push_str("\r\n");
+
+        // the default limit is 100
+        let mut raw = BytesMut::from(many.as_str());
+        let ctx = ParseContext {
+            cached_headers: &mut None,
+            req_method: &mut None,
+            h1_headers_buf: None,
+            preserve_header_case: false,
+            ignore_conflicting_content_length: false,
+        };
+        assert_eq!(Server::parse(&mut raw, ctx).unwrap_err(), Parse::TooLarge);
+
+        let mut raw = BytesMut::from(many.as_str());
+        let ctx = ParseContext {
+            cached_headers: &mut None,
+            req_method: &mut None,
+            h1_headers_buf: Some(&mut HeadersBuf::new(200)),
+            preserve_header_case: false,
+            ignore_conflicting_content_length: false,
+        };
+        let msg = Server::parse(&mut raw, ctx).unwrap().unwrap();
+        assert_eq!(msg.head.headers.len(), 150);
+
+        let mut raw =
+            BytesMut::from("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nServer: hyper\r\n\r\n");
+        let ctx = ParseContext {
+            cached_headers: &mut None,
+            req_method: &mut Some(crate::Method::GET),
+            h1_headers_buf: Some(&mut HeadersBuf::new(1)),
+            preserve_header_case: false,
+            ignore_conflicting_content_length: false,
+        };
+        assert_eq!(Client::parse(&mut raw, ctx).unwrap_err(), Parse::TooLarge);
+    }
+
     #[test]
     fn test_decoder_request() {
         fn parse(s: &str) -> ParsedMessage<RequestLine> {
@@ -1263,8 +1460,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut None,
                     req_method: &mut None,
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .expect("parse ok")
@@ -1278,13 +1476,30 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut None,
                     req_method: &mut None,
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .expect_err(comment)
         }
 
+        fn parse_ignoring_content_length(s: &str) -> ParsedMessage<RequestLine> {
+            let mut bytes = BytesMut::from(s);
+            Server::parse(
+                &mut bytes,
+                ParseContext {
+                    cached_headers: &mut None,
+                    req_method: &mut None,
+                    h1_headers_buf: None,
+                    preserve_header_case: false,
+                    ignore_conflicting_content_length: true,
+                },
+            )
+            .expect("parse ok")
+            .expect("parse complete")
+        }
+
         // no length or transfer-encoding means 0-length body
         assert_eq!(
             parse(
@@ -1359,26 +1574,83 @@ mod tests {
             DecodedLength::new(10)
         );
 
-        // transfer-encoding and content-length = chunked
+        // multiple content-lengths of same value are fine
         assert_eq!(
             parse(
                 "\
                  POST / HTTP/1.1\r\n\
                  content-length: 10\r\n\
-                 transfer-encoding: chunked\r\n\
+                 content-length: 10\r\n\
                  \r\n\
                  "
             )
             .decode,
-            DecodedLength::CHUNKED
+            DecodedLength::new(10)
         );
 
+        // multiple content-lengths with different values is an error
+        parse_err(
+            "\
+             POST / HTTP/1.1\r\n\
+             content-length: 10\r\n\
+             content-length: 11\r\n\
+             \r\n\
+             ",
+            "multiple content-lengths",
+        );
+
+        // expectations other than 100-continue are an error
         assert_eq!(
-            parse(
+            parse_err(
+                "\
+                 POST / HTTP/1.1\r\n\
+                 expect: 200-ok\r\n\
+                 \r\n\
+                 ",
+                "unsupported expectation",
+            ),
+            crate::error::Parse::Expect,
+        );
+
+        // transfer-encoding and content-length is an error
+        parse_err(
+            "\
+             POST / HTTP/1.1\r\n\
+             content-length: 10\r\n\
+             transfer-encoding: chunked\r\n\
+             \r\n\
+             ",
+            "content-length then transfer-encoding",
+        );
+
+        parse_err(
+            "\
+             POST / HTTP/1.1\r\n\
+             transfer-encoding: chunked\r\n\
+             content-length: 10\r\n\
+             \r\n\
+             ",
+            "transfer-encoding then content-length",
+        );
+
+        parse_err(
+            "\
+             POST / HTTP/1.1\r\n\
+             transfer-encoding: gzip\r\n\
+             content-length: 10\r\n\
+             transfer-encoding: chunked\r\n\
+             \r\n\
+             ",
+            "content-length between transfer-encodings",
+        );
+
+        // unless configured, then transfer-encoding and content-length = chunked
+        assert_eq!(
+            parse_ignoring_content_length(
                 "\
                  POST / HTTP/1.1\r\n\
-                 transfer-encoding: chunked\r\n\
                  content-length: 10\r\n\
+                 transfer-encoding: chunked\r\n\
                  \r\n\
                  "
             )
@@ -1387,12 +1659,11 @@ mod tests {
         );
 
         assert_eq!(
-            parse(
+            parse_ignoring_content_length(
                 "\
                  POST / HTTP/1.1\r\n\
-                 transfer-encoding: gzip\r\n\
-                 content-length: 10\r\n\
                  transfer-encoding: chunked\r\n\
+                 content-length: 10\r\n\
                  \r\n\
                  "
             )
@@ -1400,29 +1671,18 @@ mod tests {
             DecodedLength::CHUNKED
         );
 
-        // multiple content-lengths of same value are fine
         assert_eq!(
-            parse(
+            parse_ignoring_content_length(
                 "\
                  POST / HTTP/1.1\r\n\
+                 transfer-encoding: gzip\r\n\
                  content-length: 10\r\n\
-                 content-length: 10\r\n\
+                 transfer-encoding: chunked\r\n\
                  \r\n\
                  "
             )
             .decode,
-            DecodedLength::new(10)
-        );
-
-        // multiple content-lengths with different values is an error
-        parse_err(
-            "\
-             POST / HTTP/1.1\r\n\
-             content-length: 10\r\n\
-             content-length: 11\r\n\
-             \r\n\
-             ",
-            "multiple content-lengths",
+            DecodedLength::CHUNKED
         );
 
         // transfer-encoding that isn't chunked is an error
@@ -1482,8 +1742,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut None,
                     req_method: &mut Some(Method::GET),
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 }
             )
             .expect("parse ok")
@@ -1497,8 +1758,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut None,
                     req_method: &mut Some(m),
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .expect("parse ok")
@@ -1512,8 +1774,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut None,
                     req_method: &mut Some(Method::GET),
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .expect_err("parse should err")
@@ -1789,6 +2052,7 @@ mod tests {
                 head: &mut head,
                 body: Some(BodyLength::Known(10)),
                 keep_alive: true,
+                allow_trailer_fields: false,
                 req_method: &mut None,
                 title_case_headers: true,
             },
@@ -1809,6 +2073,7 @@ mod tests {
                 head: &mut head,
                 body: None,
                 keep_alive: true,
+                allow_trailer_fields: false,
                 req_method: &mut Some(Method::CONNECT),
                 title_case_headers: false,
             },
@@ -1819,6 +2084,78 @@ mod tests {
         assert!(encoder.is_last());
     }
 
+    #[test]
+    fn test_parse_preserve_header_case_in_request() {
+        let mut raw =
+            BytesMut::from("GET / HTTP/1.1\r\nHost: hyper.rs\r\nX-BREAD: baguette\r\n\r\n");
+        let ctx = ParseContext {
+            cached_headers: &mut None,
+            req_method: &mut None,
+            h1_headers_buf: None,
+            preserve_header_case: true,
+            ignore_conflicting_content_length: false,
+        };
+        let msg = Server::parse(&mut raw, ctx).unwrap().unwrap();
+        let orig_headers = msg
+            .head
+            .extensions
+            .get::<HeaderCaseMap>()
+            .expect("header case map");
+        assert_eq!(
+            orig_headers.get_all(&header::HOST).iter().next().unwrap(),
+            "Host"
+        );
+        assert_eq!(
+            orig_headers
+                .get_all(&HeaderName::from_static("x-bread"))
+                .iter()
+                .next()
+                .unwrap(),
+            "X-BREAD"
+        );
+    }
+
+    #[test]
+    fn test_server_response_encode_orig_case() {
+        use crate::proto::BodyLength;
+        use http::header::HeaderValue;
+
+        let mut head = MessageHead::default();
+        head.headers
+            .insert("content-length", HeaderValue::from_static("10"));
+        head.headers
+            .insert("content-type", HeaderValue::from_static("application/json"));
+        head.headers
+            .insert("x-bread", HeaderValue::from_static("baguette"));
+        head.headers
+            .append("x-bread", HeaderValue::from_static("pain"));
+
+        let mut orig_headers = HeaderCaseMap::default();
+        orig_headers.append(header::CONTENT_LENGTH, Bytes::from("CONTENT-LENGTH"));
+        orig_headers.append("x-bread", Bytes::from("X-Bread"));
+        orig_headers.append("x-bread", Bytes::from("X-BREAD"));
+        head.extensions.insert(orig_headers);
+
+        let mut vec = Vec::new();
+        Server::encode(
+            Encode {
+                head: &mut head,
+                body: Some(BodyLength::Known(10)),
+                keep_alive: true,
+                allow_trailer_fields: false,
+                req_method: &mut None,
+                title_case_headers: false,
+            },
+            &mut vec,
+        )
+        .unwrap();
+
+        let expected_response =
+            b"HTTP/1.1 200 OK\r\nCONTENT-LENGTH: 10\r\ncontent-type: application/json\r\nX-Bread: baguette\r\nX-BREAD: pain\r\ndate: ";
+
+        assert_eq!(&vec[..expected_response.len()], &expected_response[..]);
+    }
+
     #[test]
     fn parse_header_htabs() {
         let mut bytes = BytesMut::from("HTTP/1.1 200 OK\r\nserver: hello\tworld\r\n\r\n");
@@ -1827,8 +2164,9 @@ mod tests {
             ParseContext {
                 cached_headers: &mut None,
                 req_method: &mut Some(Method::GET),
-                #[cfg(feature = "ffi")]
+                h1_headers_buf: None,
                 preserve_header_case: false,
+                ignore_conflicting_content_length: false,
             },
         )
         .expect("parse ok")
@@ -1837,14 +2175,13 @@ mod tests {
         assert_eq!(parsed.head.headers["server"], "hello\tworld");
     }
 
-    #[cfg(feature = "ffi")]
     #[test]
     fn test_write_headers_orig_case_empty_value() {
         let mut headers = HeaderMap::new();
         let name = http::header::HeaderName::from_static("x-empty");
         headers.insert(&name, "".parse().expect("parse empty"));
-        let mut orig_cases = crate::ffi::HeaderCaseMap::default();
-        orig_cases.insert(name, Bytes::from_static(b"X-EmptY"));
+        let mut orig_cases = HeaderCaseMap::default();
+        orig_cases.append(name, Bytes::from_static(b"X-EmptY"));
 
         let mut dst = Vec::new();
         super::write_headers_original_case(&headers, &orig_cases, &mut dst);
@@ -1855,7 +2192,6 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "ffi")]
     #[test]
     fn test_write_headers_orig_case_multiple_entries() {
         let mut headers = HeaderMap::new();
@@ -1863,8 +2199,8 @@ mod tests {
         headers.insert(&name, "a".parse().unwrap());
         headers.append(&name, "b".parse().unwrap());
 
-        let mut orig_cases = crate::ffi::HeaderCaseMap::default();
-        orig_cases.insert(name.clone(), Bytes::from_static(b"X-Empty"));
+        let mut orig_cases = HeaderCaseMap::default();
+        orig_cases.append(name.clone(), Bytes::from_static(b"X-Empty"));
         orig_cases.append(name, Bytes::from_static(b"X-EMPTY"));
 
         let mut dst = Vec::new();
@@ -1908,8 +2244,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut headers,
                     req_method: &mut None,
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .unwrap()
@@ -1943,8 +2280,9 @@ mod tests {
                 ParseContext {
                     cached_headers: &mut headers,
                     req_method: &mut None,
-                    #[cfg(feature = "ffi")]
+                    h1_headers_buf: None,
                     preserve_header_case: false,
+                    ignore_conflicting_content_length: false,
                 },
             )
             .unwrap()
@@ -1985,6 +2323,7 @@ mod tests {
                     head: &mut head,
                     body: Some(BodyLength::Known(10)),
                     keep_alive: true,
+                    allow_trailer_fields: false,
                     req_method: &mut Some(Method::GET),
                     title_case_headers: false,
                 },
@@ -2013,6 +2352,7 @@ mod tests {
                     head: &mut head,
                     body: Some(BodyLength::Known(10)),
                     keep_alive: true,
+                    allow_trailer_fields: false,
                     req_method: &mut Some(Method::GET),
                     title_case_headers: false,
                 },
@@ -50,7 +50,6 @@ use std::marker::PhantomData;
 #[cfg(feature = "tcp")]
 use std::net::SocketAddr;
 #[cfg(feature = "runtime")]
-#[cfg(feature = "http2")]
 use std::time::Duration;
 
 use bytes::Bytes;
@@ -89,7 +88,13 @@ pub use super::tcp::{AddrIncoming, AddrStream};
 pub struct Http<E = Exec> {
     exec: E,
     h1_half_close: bool,
+    h1_ignore_conflicting_content_length: bool,
     h1_keep_alive: bool,
+    #[cfg(feature = "runtime")]
+    h1_header_read_timeout: Option<Duration>,
+    h1_max_headers: Option<usize>,
+    h1_preserve_header_case: bool,
+    h1_writev: Option<bool>,
     #[cfg(feature = "http2")]
     h2_builder: proto::h2::server::Config,
     mode: ConnectionMode,
@@ -240,7 +245,13 @@ impl Http {
         Http {
             exec: Exec::Default,
             h1_half_close: false,
+            h1_ignore_conflicting_content_length: false,
             h1_keep_alive: true,
+            #[cfg(feature = "runtime")]
+            h1_header_read_timeout: None,
+            h1_max_headers: None,
+            h1_preserve_header_case: false,
+            h1_writev: None,
             #[cfg(feature = "http2")]
             h2_builder: Default::default(),
             mode: ConnectionMode::default(),
@@ -293,6 +304,103 @@ impl<E> Http<E> {
         self
     }
 
+    /// Set a timeout for reading client request headers.
+    ///
+    /// If a client does not transmit the entire message head within this
+    /// time, the connection is closed. If part of a head had been received,
+    /// a `408 Request Timeout` response is sent first.
+    ///
+    /// The deadline restarts each time the connection waits for a new
+    /// request, so it also bounds how long a keep-alive connection may sit
+    /// idle.
+    ///
+    /// Default is `None`.
+    ///
+    /// # Cargo Feature
+    ///
+    /// Requires the `runtime` cargo feature to be enabled.
+    #[cfg(feature = "runtime")]
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "http1", feature = "runtime"))))]
+    pub fn http1_header_read_timeout(&mut self, read_timeout: Duration) -> &mut Self {
+        self.h1_header_read_timeout = Some(read_timeout);
+        self
+    }
+
+    /// Set the maximum number of headers allowed in an HTTP/1 message head.
+    ///
+    /// Messages with more headers are rejected as too large. When left
+    /// unset, a limit of 100 is used and the header slots are kept on the
+    /// stack; setting this option allocates them on the heap instead.
+    ///
+    /// Default is 100.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_max_headers(&mut self, val: usize) -> &mut Self {
+        self.h1_max_headers = Some(val);
+        self
+    }
+
+    /// Set whether HTTP/1 connections should accept requests with both
+    /// `Transfer-Encoding` and `Content-Length` headers.
+    ///
+    /// By default such requests are rejected with `400 Bad Request`, since
+    /// a proxy in front of the server may frame them differently, allowing
+    /// request smuggling. When enabled, the `Content-Length` is ignored and
+    /// the request is framed by its `Transfer-Encoding` instead.
+    ///
+    /// Default is false.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_ignore_conflicting_content_length(&mut self, enabled: bool) -> &mut Self {
+        self.h1_ignore_conflicting_content_length = enabled;
+        self
+    }
+
+    /// Set whether HTTP/1 connections will remember the original casing of
+    /// received header names.
+    ///
+    /// The casing is kept alongside the request, and is used again when a
+    /// message carrying it (such as a request forwarded by a proxy) is
+    /// written out, so header names go back on the wire as they came in.
+    /// Only the casing of names, and the order of repeated values of one
+    /// name, are preserved; headers with different names are still written
+    /// in `HeaderMap` order.
+    ///
+    /// The casing is stored in the request's `Extensions`, so it only
+    /// survives if those extensions are moved into the outgoing message. A
+    /// message rebuilt with `Request::builder()` or `Response::builder()`
+    /// loses it.
+    ///
+    /// Note that this setting does not affect HTTP/2.
+    ///
+    /// Default is false.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_preserve_header_case(&mut self, enabled: bool) -> &mut Self {
+        self.h1_preserve_header_case = enabled;
+        self
+    }
+
+    /// Set whether HTTP/1 connections should try to use vectored writes,
+    /// or always flatten into a single buffer.
+    ///
+    /// Note that setting this to false may mean more copies of body data,
+    /// but may also improve performance when an IO transport doesn't
+    /// support vectored writes well, such as most TLS implementations.
+    ///
+    /// Setting this to true will force hyper to use queued strategy
+    /// which may eliminate unnecessary cloning on some TLS backends
+    ///
+    /// Default is `auto`. In this mode hyper will try to guess which
+    /// mode to use
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_writev(&mut self, val: bool) -> &mut Self {
+        self.h1_writev = Some(val);
+        self
+    }
+
     /// Sets whether HTTP2 is required.
     ///
     /// Default is false
@@ -465,7 +573,13 @@ impl<E> Http<E> {
         Http {
             exec,
             h1_half_close: self.h1_half_close,
+            h1_ignore_conflicting_content_length: self.h1_ignore_conflicting_content_length,
             h1_keep_alive: self.h1_keep_alive,
+            #[cfg(feature = "runtime")]
+            h1_header_read_timeout: self.h1_header_read_timeout,
+            h1_max_headers: self.h1_max_headers,
+            h1_preserve_header_case: self.h1_preserve_header_case,
+            h1_writev: self.h1_writev,
             #[cfg(feature = "http2")]
             h2_builder: self.h2_builder,
             mode: self.mode,
@@ -521,6 +635,26 @@ impl<E> Http<E> {
                 if self.h1_half_close {
                     conn.set_allow_half_close();
                 }
+                if self.h1_ignore_conflicting_content_length {
+                    conn.set_ignore_conflicting_content_length();
+                }
+                #[cfg(feature = "runtime")]
+                if let Some(read_timeout) = self.h1_header_read_timeout {
+                    conn.set_http1_header_read_timeout(read_timeout);
+                }
+                if let Some(max) = self.h1_max_headers {
+                    conn.set_http1_max_headers(max);
+                }
+                if self.h1_preserve_header_case {
+                    conn.set_preserve_header_case();
+                }
+                if let Some(writev) = self.h1_writev {
+                    if writev {
+                        conn.set_write_strategy_queue();
+                    } else {
+                        conn.set_write_strategy_flatten();
+                    }
+                }
                 conn.set_flush_pipeline(self.pipeline_flush);
                 if let Some(max) = self.max_buf_size {
                     conn.set_max_buf_size(max);
@@ -210,6 +210,103 @@ impl<I, E> Builder<I, E> {
         self
     }
 
+    /// Set a timeout for reading client request headers.
+    ///
+    /// If a client does not transmit the entire message head within this
+    /// time, the connection is closed. If part of a head had been received,
+    /// a `408 Request Timeout` response is sent first.
+    ///
+    /// The deadline restarts each time the connection waits for a new
+    /// request, so it also bounds how long a keep-alive connection may sit
+    /// idle.
+    ///
+    /// Default is `None`.
+    ///
+    /// # Cargo Feature
+    ///
+    /// Requires the `runtime` cargo feature to be enabled.
+    #[cfg(feature = "runtime")]
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "http1", feature = "runtime"))))]
+    pub fn http1_header_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.protocol.http1_header_read_timeout(read_timeout);
+        self
+    }
+
+    /// Set the maximum number of headers allowed in an HTTP/1 message head.
+    ///
+    /// Messages with more headers are rejected as too large. When left
+    /// unset, a limit of 100 is used and the header slots are kept on the
+    /// stack; setting this option allocates them on the heap instead.
+    ///
+    /// Default is 100.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_max_headers(mut self, val: usize) -> Self {
+        self.protocol.http1_max_headers(val);
+        self
+    }
+
+    /// Set whether HTTP/1 connections should accept requests with both
+    /// `Transfer-Encoding` and `Content-Length` headers.
+    ///
+    /// By default such requests are rejected with `400 Bad Request`, since
+    /// a proxy in front of the server may frame them differently, allowing
+    /// request smuggling. When enabled, the `Content-Length` is ignored and
+    /// the request is framed by its `Transfer-Encoding` instead.
+    ///
+    /// Default is false.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_ignore_conflicting_content_length(mut self, val: bool) -> Self {
+        self.protocol.http1_ignore_conflicting_content_length(val);
+        self
+    }
+
+    /// Set whether HTTP/1 connections will remember the original casing of
+    /// received header names.
+    ///
+    /// The casing is kept alongside the request, and is used again when a
+    /// message carrying it (such as a request forwarded by a proxy) is
+    /// written out, so header names go back on the wire as they came in.
+    /// Only the casing of names, and the order of repeated values of one
+    /// name, are preserved; headers with different names are still written
+    /// in `HeaderMap` order.
+    ///
+    /// The casing is stored in the request's `Extensions`, so it only
+    /// survives if those extensions are moved into the outgoing message. A
+    /// message rebuilt with `Request::builder()` or `Response::builder()`
+    /// loses it.
+    ///
+    /// Note that this setting does not affect HTTP/2.
+    ///
+    /// Default is false.
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_preserve_header_case(mut self, val: bool) -> Self {
+        self.protocol.http1_preserve_header_case(val);
+        self
+    }
+
+    /// Set whether HTTP/1 connections should try to use vectored writes,
+    /// or always flatten into a single buffer.
+    ///
+    /// Note that setting this to false may mean more copies of body data,
+    /// but may also improve performance when an IO transport doesn't
+    /// support vectored writes well, such as most TLS implementations.
+    ///
+    /// Setting this to true will force hyper to use queued strategy
+    /// which may eliminate unnecessary cloning on some TLS backends
+    ///
+    /// Default is `auto`. In this mode hyper will try to guess which
+    /// mode to use
+    #[cfg(feature = "http1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http1")))]
+    pub fn http1_writev(mut self, val: bool) -> Self {
+        self.protocol.http1_writev(val);
+        self
+    }
+
     /// Set the maximum buffer size.
     ///
     /// Default is ~ 400kb.
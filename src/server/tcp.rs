@@ -36,6 +36,19 @@ impl AddrIncoming {
             .set_nonblocking(true)
             .map_err(crate::Error::new_listen)?;
         let listener = TcpListener::from_std(std_listener).map_err(crate::Error::new_listen)?;
+        AddrIncoming::from_listener(listener)
+    }
+
+    /// Creates a new `AddrIncoming` binding to provided socket address.
+    pub fn bind(addr: &SocketAddr) -> crate::Result<Self> {
+        AddrIncoming::new(addr)
+    }
+
+    /// Creates a new `AddrIncoming` from an existing `tokio::net::TcpListener`.
+    ///
+    /// This is useful when the listening socket was created elsewhere, such
+    /// as one inherited from a process supervisor.
+    pub fn from_listener(listener: TcpListener) -> crate::Result<Self> {
         let addr = listener.local_addr().map_err(crate::Error::new_listen)?;
         Ok(AddrIncoming {
             listener,
@@ -47,11 +60,6 @@ impl AddrIncoming {
         })
     }
 
-    /// Creates a new `AddrIncoming` binding to provided socket address.
-    pub fn bind(addr: &SocketAddr) -> crate::Result<Self> {
-        AddrIncoming::new(addr)
-    }
-
     /// Get the local address bound to this listener.
     pub fn local_addr(&self) -> SocketAddr {
         self.addr
@@ -101,7 +109,7 @@ impl AddrIncoming {
 
         loop {
             match ready!(self.listener.poll_accept(cx)) {
-                Ok((socket, addr)) => {
+                Ok((socket, remote_addr)) => {
                     if let Some(dur) = self.tcp_keepalive_timeout {
                         // Convert the Tokio `TcpStream` into a `socket2` socket
                         // so we can call `set_keepalive`.
@@ -147,7 +155,16 @@ impl AddrIncoming {
                     if let Err(e) = socket.set_nodelay(self.tcp_nodelay) {
                         trace!("error trying to set TCP nodelay: {}", e);
                     }
-                    return Poll::Ready(Ok(AddrStream::new(socket, addr)));
+                    let local_addr = match socket.local_addr() {
+                        Ok(addr) => addr,
+                        Err(e) => {
+                            // Only this connection is affected, so drop it
+                            // and keep accepting.
+                            debug!("error getting local address of accepted connection: {}", e);
+                            continue;
+                        }
+                    };
+                    return Poll::Ready(Ok(AddrStream::new(socket, remote_addr, local_addr)));
                 }
                 Err(e) => {
                     // Connection errors can be ignored directly, continue by
@@ -236,13 +253,19 @@ mod addr_stream {
         #[pin]
         inner: TcpStream,
         pub(super) remote_addr: SocketAddr,
+        pub(super) local_addr: SocketAddr,
     }
 
     impl AddrStream {
-        pub(super) fn new(tcp: TcpStream, addr: SocketAddr) -> AddrStream {
+        pub(super) fn new(
+            tcp: TcpStream,
+            remote_addr: SocketAddr,
+            local_addr: SocketAddr,
+        ) -> AddrStream {
             AddrStream {
                 inner: tcp,
-                remote_addr: addr,
+                remote_addr,
+                local_addr,
             }
         }
 
@@ -252,6 +275,15 @@ mod addr_stream {
             self.remote_addr
         }
 
+        /// Returns the local address of this connection.
+        ///
+        /// Connections whose local address can't be read are dropped by
+        /// `AddrIncoming`, and never reach the service.
+        #[inline]
+        pub fn local_addr(&self) -> SocketAddr {
+            self.local_addr
+        }
+
         /// Consumes the AddrStream and returns the underlying IO object
         #[inline]
         pub fn into_inner(self) -> TcpStream {
@@ -23,10 +23,10 @@ use tokio::net::{TcpListener, TcpStream as TkTcpStream};
 
 use hyper::body::HttpBody as _;
 use hyper::client::Client;
-use hyper::server::conn::Http;
+use hyper::server::conn::{AddrIncoming, AddrStream, Http};
 use hyper::server::Server;
 use hyper::service::{make_service_fn, service_fn};
-use hyper::{Body, Request, Response, StatusCode, Version};
+use hyper::{Body, HeaderMap, Request, Response, StatusCode, Version};
 
 mod support;
 
@@ -955,6 +955,346 @@ fn http_10_request_receives_http_10_response() {
     assert_eq!(s(&buf[..expected.len()]), expected);
 }
 
+#[tokio::test]
+async fn http1_writev_disabled() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        req.write_all(
+            b"\
+            GET / HTTP/1.1\r\n\
+            Host: example.domain\r\n\
+            Connection: close\r\n\
+            \r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .http1_writev(false)
+        .serve_connection(socket, HelloWorld)
+        .await
+        .expect("serve_connection");
+
+    let buf = child.join().unwrap();
+    let res = s(&buf);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"), "{:?}", res);
+    assert!(res.ends_with(HELLO), "{:?}", res);
+}
+
+#[tokio::test]
+async fn http1_response_trailers() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        req.write_all(
+            b"\
+            GET / HTTP/1.1\r\n\
+            Host: example.domain\r\n\
+            TE: trailers\r\n\
+            Connection: close\r\n\
+            \r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .serve_connection(
+            socket,
+            service_fn(|_| async move {
+                let (mut tx, body) = Body::channel();
+                tokio::spawn(async move {
+                    tx.send_data("hello".into()).await.unwrap();
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert("chunky-trailer", "header data".parse().unwrap());
+                    tx.send_trailers(trailers).await.unwrap();
+                });
+                Ok::<_, hyper::Error>(
+                    Response::builder()
+                        .header("trailer", "chunky-trailer")
+                        .body(body)
+                        .unwrap(),
+                )
+            }),
+        )
+        .await
+        .expect("serve_connection");
+
+    let buf = child.join().unwrap();
+    let res = s(&buf);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"), "{:?}", res);
+    assert!(
+        res.ends_with("5\r\nhello\r\n0\r\nchunky-trailer: header data\r\n\r\n"),
+        "{:?}",
+        res
+    );
+}
+
+#[tokio::test]
+async fn http1_response_trailers_not_accepted() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        req.write_all(
+            b"\
+            GET / HTTP/1.1\r\n\
+            Host: example.domain\r\n\
+            Connection: close\r\n\
+            \r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .serve_connection(
+            socket,
+            service_fn(|_| async move {
+                let (mut tx, body) = Body::channel();
+                tokio::spawn(async move {
+                    tx.send_data("hello".into()).await.unwrap();
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert("chunky-trailer", "header data".parse().unwrap());
+                    tx.send_trailers(trailers).await.unwrap();
+                });
+                Ok::<_, hyper::Error>(
+                    Response::builder()
+                        .header("trailer", "chunky-trailer")
+                        .body(body)
+                        .unwrap(),
+                )
+            }),
+        )
+        .await
+        .expect("serve_connection");
+
+    let buf = child.join().unwrap();
+    let res = s(&buf);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"), "{:?}", res);
+    assert!(res.ends_with("5\r\nhello\r\n0\r\n\r\n"), "{:?}", res);
+}
+
+#[tokio::test]
+async fn http1_response_trailers_pending() {
+    // A body that isn't fused, and whose trailers aren't ready right away.
+    struct PendingTrailers {
+        data: Option<hyper::body::Bytes>,
+        data_done: bool,
+        trailers_pending: bool,
+    }
+
+    impl hyper::body::HttpBody for PendingTrailers {
+        type Data = hyper::body::Bytes;
+        type Error = hyper::Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            assert!(!self.data_done, "poll_data called after returning None");
+            match self.data.take() {
+                Some(data) => Poll::Ready(Some(Ok(data))),
+                None => {
+                    self.data_done = true;
+                    Poll::Ready(None)
+                }
+            }
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            if self.trailers_pending {
+                self.trailers_pending = false;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let mut trailers = HeaderMap::new();
+            trailers.insert("chunky-trailer", "header data".parse().unwrap());
+            Poll::Ready(Ok(Some(trailers)))
+        }
+    }
+
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        req.write_all(
+            b"\
+            GET / HTTP/1.1\r\n\
+            Host: example.domain\r\n\
+            TE: trailers\r\n\
+            Connection: close\r\n\
+            \r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .serve_connection(
+            socket,
+            service_fn(|_| async move {
+                let body = PendingTrailers {
+                    data: Some("hello".into()),
+                    data_done: false,
+                    trailers_pending: true,
+                };
+                Ok::<_, hyper::Error>(
+                    Response::builder()
+                        .header("trailer", "chunky-trailer")
+                        .body(body)
+                        .unwrap(),
+                )
+            }),
+        )
+        .await
+        .expect("serve_connection");
+
+    let buf = child.join().unwrap();
+    let res = s(&buf);
+    assert!(
+        res.ends_with("5\r\nhello\r\n0\r\nchunky-trailer: header data\r\n\r\n"),
+        "{:?}",
+        res
+    );
+}
+
+#[tokio::test]
+async fn http1_preserve_header_case() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        req.write_all(
+            b"\
+            GET / HTTP/1.1\r\n\
+            Host: example.domain\r\n\
+            X-Bread: Baguette\r\n\
+            X-BREAD: Pain\r\n\
+            Connection: close\r\n\
+            \r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .http1_preserve_header_case(true)
+        .serve_connection(
+            socket,
+            service_fn(|req: Request<Body>| async move {
+                // echo the request headers back, keeping their casing
+                let (parts, _) = req.into_parts();
+                let mut res = Response::new(Body::empty());
+                *res.headers_mut() = parts.headers;
+                *res.extensions_mut() = parts.extensions;
+                Ok::<_, hyper::Error>(res)
+            }),
+        )
+        .await
+        .expect("serve_connection");
+
+    let buf = child.join().unwrap();
+    let res = s(&buf);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"), "{:?}", res);
+    assert!(res.contains("\r\nHost: example.domain\r\n"), "{:?}", res);
+    assert!(
+        res.contains("\r\nX-Bread: Baguette\r\nX-BREAD: Pain\r\n"),
+        "{:?}",
+        res
+    );
+    assert!(res.contains("\r\nConnection: close\r\n"), "{:?}", res);
+}
+
+#[tokio::test]
+async fn http1_header_read_timeout_sends_408() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        // never finish the head
+        req.write_all(b"GET / HTTP/1.1\r\nHost: example.domain\r\n")
+            .unwrap();
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    let err = Http::new()
+        .http1_header_read_timeout(Duration::from_millis(100))
+        .serve_connection(socket, unreachable_service())
+        .await
+        .expect_err("header read timeout");
+    assert!(err.is_timeout(), "{:?}", err);
+
+    let buf = child.join().unwrap();
+    let expected = "HTTP/1.1 408 Request Timeout\r\n";
+    assert_eq!(s(&buf[..expected.len()]), expected);
+}
+
+#[tokio::test]
+async fn http1_header_read_timeout_closes_idle_connection() {
+    let _ = pretty_env_logger::try_init();
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let child = thread::spawn(move || {
+        let mut req = connect(&addr);
+        let mut buf = vec![];
+        req.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .http1_header_read_timeout(Duration::from_millis(100))
+        .serve_connection(socket, unreachable_service())
+        .await
+        .expect("idle connection closes cleanly");
+
+    assert!(child.join().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn disable_keep_alive_mid_request() {
     let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
@@ -1526,6 +1866,88 @@ async fn illegal_request_length_returns_400_response() {
         .expect_err("illegal Content-Length should error");
 }
 
+#[tokio::test]
+async fn transfer_encoding_and_content_length_returns_400_response() {
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut tcp = connect(&addr);
+        tcp.write_all(
+            b"\
+            POST / HTTP/1.1\r\n\
+            Content-Length: 5\r\n\
+            Transfer-Encoding: chunked\r\n\
+            \r\n\
+            0\r\n\r\n\
+        ",
+        )
+        .unwrap();
+        let mut buf = [0; 256];
+        tcp.read(&mut buf).unwrap();
+
+        let expected = "HTTP/1.1 400 ";
+        assert_eq!(s(&buf[..expected.len()]), expected);
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .serve_connection(socket, HelloWorld)
+        .await
+        .expect_err("Transfer-Encoding with Content-Length should error");
+}
+
+#[tokio::test]
+async fn unsupported_expectation_returns_417_response() {
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut tcp = connect(&addr);
+        tcp.write_all(b"POST / HTTP/1.1\r\nExpect: 200-ok\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        let mut buf = [0; 256];
+        tcp.read(&mut buf).unwrap();
+
+        let expected = "HTTP/1.1 417 ";
+        assert_eq!(s(&buf[..expected.len()]), expected);
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .serve_connection(socket, HelloWorld)
+        .await
+        .expect_err("unsupported Expect should error");
+}
+
+#[tokio::test]
+async fn too_many_headers_returns_431_response() {
+    let listener = tcp_bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut tcp = connect(&addr);
+        let mut req = String::from("GET / HTTP/1.1\r\n");
+        for i in 0..10 {
+            req.push_str(&format!("x-header-{}: {}\r\n", i, i));
+        }
+        req.push_str("\r\n");
+        tcp.write_all(req.as_bytes()).unwrap();
+        let mut buf = [0; 256];
+        tcp.read(&mut buf).unwrap();
+
+        let expected = "HTTP/1.1 431 ";
+        assert_eq!(s(&buf[..expected.len()]), expected);
+    });
+
+    let (socket, _) = listener.accept().await.unwrap();
+    Http::new()
+        .http1_max_headers(5)
+        .serve_connection(socket, HelloWorld)
+        .await
+        .expect_err("too many headers should error");
+}
+
 #[cfg(feature = "http1")]
 #[test]
 #[should_panic]
@@ -1594,6 +2016,61 @@ fn streaming_body() {
     assert_eq!(buf.len(), 100_789, "full streamed body read");
 }
 
+#[tokio::test]
+async fn addr_incoming_from_listener() {
+    let _ = pretty_env_logger::try_init();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let incoming = AddrIncoming::from_listener(listener).expect("from_listener");
+    assert_eq!(incoming.local_addr(), addr);
+
+    let server = Server::builder(incoming).serve(make_service_fn(|_| async move {
+        Ok::<_, hyper::Error>(service_fn(|_| async move {
+            Ok::<_, hyper::Error>(Response::new(Body::empty()))
+        }))
+    }));
+
+    tokio::task::spawn(async move {
+        server.await.expect("server");
+    });
+
+    let uri = format!("http://{}", addr).parse().unwrap();
+    let res = Client::new().get(uri).await.expect("client.get");
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn addr_stream_local_addr() {
+    let _ = pretty_env_logger::try_init();
+
+    let incoming = AddrIncoming::bind(&([127, 0, 0, 1], 0).into()).expect("bind");
+    let addr = incoming.local_addr();
+
+    let (tx, rx) = oneshot::channel();
+    let mut tx = Some(tx);
+    let server = Server::builder(incoming).serve(make_service_fn(move |conn: &AddrStream| {
+        if let Some(tx) = tx.take() {
+            let _ = tx.send((conn.local_addr(), conn.remote_addr()));
+        }
+        async move {
+            Ok::<_, hyper::Error>(service_fn(|_| async move {
+                Ok::<_, hyper::Error>(Response::new(Body::empty()))
+            }))
+        }
+    }));
+
+    tokio::task::spawn(async move {
+        server.await.expect("server");
+    });
+
+    let tcp = TkTcpStream::connect(addr).await.expect("connect");
+    let (local_addr, remote_addr) = rx.await.expect("conn addrs");
+    assert_eq!(local_addr, addr);
+    assert_eq!(remote_addr, tcp.local_addr().unwrap());
+}
+
 #[test]
 fn http1_response_with_http2_version() {
     let server = serve();